@@ -0,0 +1,173 @@
+use crate::med::MedQrng;
+use crate::traits::{Qrng, SyncQrng};
+use anyhow::{anyhow, Result};
+use std::sync::mpsc as std_mpsc;
+use std::thread::{self, JoinHandle};
+use tokio::sync::oneshot;
+
+///////////////////////////////////////////////////////////////////////////////
+// Dedicated COM apartment thread + async API
+//
+// COM here is apartment-threaded (COINIT_APARTMENTTHREADED) and MedQrng's
+// Drop calls CoUninitialize unconditionally, so the object can't safely
+// cross threads or be driven from an async runtime directly. AsyncQrng
+// spins up one long-lived STA worker thread that owns the IDispatch
+// pointer, performs CoInitializeEx/CoUninitialize exactly once on that
+// thread, and services requests sent over a command channel.
+///////////////////////////////////////////////////////////////////////////////
+
+enum Command {
+    RandInt32(oneshot::Sender<Result<i32>>),
+    RandUniform(oneshot::Sender<Result<f64>>),
+    RandNormal(oneshot::Sender<Result<f64>>),
+    RandBytes(i32, oneshot::Sender<Result<Vec<u8>>>),
+    DeviceId(oneshot::Sender<Result<String>>),
+    Clear(oneshot::Sender<Result<()>>),
+    Reset(oneshot::Sender<Result<()>>),
+    Shutdown,
+}
+
+/// `Send + Sync` async handle to a `MedQrng` backed by a dedicated COM
+/// worker thread.
+///
+/// Every method sends a [`Command`] over a channel to the worker and awaits
+/// a oneshot reply, so `AsyncQrng` can be held and polled from `tokio`
+/// tasks without ever touching the `IDispatch` pointer from more than one
+/// thread.
+pub struct AsyncQrng {
+    commands: std_mpsc::Sender<Command>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl AsyncQrng {
+    /// Spawns the COM worker thread and opens the device on it, blocking
+    /// until the device is ready (or has failed to open).
+    pub fn spawn() -> Result<Self> {
+        let (commands, rx) = std_mpsc::channel::<Command>();
+        let (ready_tx, ready_rx) = std_mpsc::channel::<Result<()>>();
+        let worker = thread::Builder::new()
+            .name("med-qrng-com".into())
+            .spawn(move || worker_loop(rx, ready_tx))
+            .map_err(|e| anyhow!("failed to spawn COM worker thread: {e}"))?;
+        ready_rx
+            .recv()
+            .map_err(|_| anyhow!("COM worker thread exited before initializing"))??;
+        Ok(Self {
+            commands,
+            worker: Some(worker),
+        })
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.commands
+            .send(command)
+            .map_err(|_| anyhow!("COM worker thread is not running"))
+    }
+
+    /// Retrieves a 32-bit random integer from the RandInt32 property.
+    pub async fn rand_int32(&self) -> Result<i32> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::RandInt32(tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+
+    /// Retrieves a uniform random double (in [0,1)) from RandUniform.
+    pub async fn rand_uniform(&self) -> Result<f64> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::RandUniform(tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+
+    /// Retrieves a normally distributed random double from RandNormal.
+    pub async fn rand_normal(&self) -> Result<f64> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::RandNormal(tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+
+    /// Retrieves random bytes (SAFEARRAY of VT_UI1) from RandBytes.
+    pub async fn rand_bytes(&self, length: i32) -> Result<Vec<u8>> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::RandBytes(length, tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+
+    /// Retrieves the device serial number (BSTR) from DeviceId.
+    pub async fn device_id(&self) -> Result<String> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::DeviceId(tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+
+    /// Calls the Clear() method.
+    pub async fn clear(&self) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::Clear(tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+
+    /// Calls the Reset() method.
+    pub async fn reset(&self) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::Reset(tx))?;
+        rx.await.map_err(|_| anyhow!("COM worker thread dropped the reply channel"))?
+    }
+}
+
+impl Drop for AsyncQrng {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::Shutdown);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// `AsyncQrng` is a QRNG backend like any other, just not a `SyncQrng` —
+/// its methods are `async fn`s rather than blocking calls.
+impl Qrng for AsyncQrng {}
+
+/// Runs on the dedicated STA thread for the lifetime of the `AsyncQrng`
+/// handle. `MedQrng` is constructed and dropped here, so
+/// `CoInitializeEx`/`CoUninitialize` each run exactly once on this thread.
+fn worker_loop(commands: std_mpsc::Receiver<Command>, ready: std_mpsc::Sender<Result<()>>) {
+    let qrng = match MedQrng::new() {
+        Ok(qrng) => {
+            let _ = ready.send(Ok(()));
+            qrng
+        }
+        Err(err) => {
+            let _ = ready.send(Err(err));
+            return;
+        }
+    };
+
+    while let Ok(command) = commands.recv() {
+        match command {
+            Command::RandInt32(tx) => {
+                let _ = tx.send(qrng.rand_int32());
+            }
+            Command::RandUniform(tx) => {
+                let _ = tx.send(qrng.rand_uniform());
+            }
+            Command::RandNormal(tx) => {
+                let _ = tx.send(qrng.rand_normal());
+            }
+            Command::RandBytes(length, tx) => {
+                let _ = tx.send(qrng.rand_bytes(length));
+            }
+            Command::DeviceId(tx) => {
+                let _ = tx.send(qrng.device_id());
+            }
+            Command::Clear(tx) => {
+                let _ = tx.send(qrng.clear());
+            }
+            Command::Reset(tx) => {
+                let _ = tx.send(qrng.reset());
+            }
+            Command::Shutdown => break,
+        }
+    }
+    // `qrng` drops here, calling CoUninitialize on the same thread that
+    // initialized it.
+}
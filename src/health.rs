@@ -0,0 +1,214 @@
+///////////////////////////////////////////////////////////////////////////////
+// NIST SP 800-90B continuous health tests
+//
+// Runs the Repetition Count Test (RCT) and Adaptive Proportion Test (APT)
+// over the raw byte stream returned by a backend's `rand_bytes`, so callers
+// can detect a degraded device without waiting for an offline statistical
+// test suite to notice.
+///////////////////////////////////////////////////////////////////////////////
+
+/// Tunables for [`HealthMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthConfig {
+    /// Assumed per-sample min-entropy `H`, in bits. SP 800-90B calls this
+    /// the noise source's min-entropy estimate; 7.9 is a conservative
+    /// default for an 8-bit-wide source that isn't quite ideal.
+    pub min_entropy_bits: f64,
+    /// False-positive rate `alpha` used to derive both cutoffs.
+    pub false_positive_rate: f64,
+    /// APT window size `W`. SP 800-90B specifies 1024 for byte-valued
+    /// samples (512 when samples are individual bits).
+    pub window: usize,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            min_entropy_bits: 7.9,
+            false_positive_rate: 2f64.powi(-20),
+            window: 1024,
+        }
+    }
+}
+
+/// Which continuous test raised the alarm, and the state it alarmed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthAlarm {
+    /// The Repetition Count Test saw the same sample `run_length` times in
+    /// a row, at or beyond its cutoff.
+    RepetitionCount { run_length: usize },
+    /// The Adaptive Proportion Test saw the window's first sample repeat
+    /// `count` times within a `window`-sample window, more than its cutoff
+    /// allows.
+    AdaptiveProportion { count: usize, window: usize },
+}
+
+/// Stateful SP 800-90B continuous health monitor.
+///
+/// Feed it every sample as it comes off the device via [`observe`]; state
+/// (previous value, run length, window position) persists across calls.
+/// Call [`reset`] after the device's own `Reset()` so stale run/window
+/// state from before the reset doesn't trigger a false alarm.
+///
+/// [`observe`]: HealthMonitor::observe
+/// [`reset`]: HealthMonitor::reset
+pub struct HealthMonitor {
+    config: HealthConfig,
+    rct_cutoff: usize,
+    apt_cutoff: usize,
+    prev_sample: Option<u8>,
+    run_length: usize,
+    window_first: Option<u8>,
+    window_pos: usize,
+    window_match_count: usize,
+    last_alarm: Option<HealthAlarm>,
+}
+
+impl HealthMonitor {
+    pub fn new(config: HealthConfig) -> Self {
+        let rct_cutoff = rct_cutoff(config.min_entropy_bits, config.false_positive_rate);
+        let apt_cutoff = apt_cutoff(config.min_entropy_bits, config.false_positive_rate, config.window);
+        Self {
+            config,
+            rct_cutoff,
+            apt_cutoff,
+            prev_sample: None,
+            run_length: 0,
+            window_first: None,
+            window_pos: 0,
+            window_match_count: 0,
+            last_alarm: None,
+        }
+    }
+
+    /// Feeds a buffer of raw samples (e.g. a `rand_bytes` result) through
+    /// both continuous tests.
+    pub fn observe(&mut self, samples: &[u8]) {
+        for &sample in samples {
+            self.observe_rct(sample);
+            self.observe_apt(sample);
+        }
+    }
+
+    fn observe_rct(&mut self, sample: u8) {
+        match self.prev_sample {
+            Some(prev) if prev == sample => {
+                self.run_length += 1;
+                if self.run_length >= self.rct_cutoff {
+                    self.last_alarm = Some(HealthAlarm::RepetitionCount {
+                        run_length: self.run_length,
+                    });
+                }
+            }
+            _ => self.run_length = 1,
+        }
+        self.prev_sample = Some(sample);
+    }
+
+    fn observe_apt(&mut self, sample: u8) {
+        let first = *self.window_first.get_or_insert(sample);
+        if self.window_pos == 0 {
+            // The window's defining sample doesn't count against itself.
+            self.window_pos = 1;
+            return;
+        }
+        if sample == first {
+            self.window_match_count += 1;
+        }
+        self.window_pos += 1;
+        if self.window_pos >= self.config.window {
+            if self.window_match_count > self.apt_cutoff {
+                self.last_alarm = Some(HealthAlarm::AdaptiveProportion {
+                    count: self.window_match_count,
+                    window: self.config.window,
+                });
+            }
+            self.window_first = None;
+            self.window_pos = 0;
+            self.window_match_count = 0;
+        }
+    }
+
+    /// The most recent alarm raised by either test, if any.
+    pub fn last_alarm(&self) -> Option<HealthAlarm> {
+        self.last_alarm
+    }
+
+    /// Clears all accumulated run/window state and the last alarm. Call
+    /// this after the device's own `Reset()`, since a fresh device run
+    /// shouldn't be compared against samples from before the reset.
+    pub fn reset(&mut self) {
+        self.prev_sample = None;
+        self.run_length = 0;
+        self.window_first = None;
+        self.window_pos = 0;
+        self.window_match_count = 0;
+        self.last_alarm = None;
+    }
+}
+
+/// RCT cutoff: `C = 1 + ceil(-log2(alpha) / H)`.
+fn rct_cutoff(min_entropy_bits: f64, alpha: f64) -> usize {
+    (1.0 + (-alpha.log2() / min_entropy_bits).ceil()) as usize
+}
+
+/// APT cutoff: the smallest `C` such that `P(X > C) <= alpha`, where
+/// `X ~ Binomial(window - 1, 2^-H)` per SP 800-90B section 4.4.2.
+fn apt_cutoff(min_entropy_bits: f64, alpha: f64, window: usize) -> usize {
+    let p = 2f64.powf(-min_entropy_bits);
+    let trials = (window - 1) as u64;
+    let mut cumulative = 0.0;
+    let mut c = 0u64;
+    loop {
+        cumulative += binomial_pmf(trials, c, p);
+        if 1.0 - cumulative <= alpha || c >= trials {
+            return c as usize;
+        }
+        c += 1;
+    }
+}
+
+fn binomial_pmf(n: u64, k: u64, p: f64) -> f64 {
+    let log_comb = ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k);
+    let log_p = log_comb + (k as f64) * p.ln() + ((n - k) as f64) * (1.0 - p).ln();
+    log_p.exp()
+}
+
+fn ln_factorial(n: u64) -> f64 {
+    (1..=n).map(|x| (x as f64).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repetition_count_test_alarms_on_stuck_output() {
+        let mut monitor = HealthMonitor::new(HealthConfig::default());
+        let stuck = vec![0x42u8; monitor.rct_cutoff + 1];
+        monitor.observe(&stuck);
+        assert!(matches!(
+            monitor.last_alarm(),
+            Some(HealthAlarm::RepetitionCount { .. })
+        ));
+    }
+
+    #[test]
+    fn healthy_alternating_stream_does_not_alarm() {
+        let mut monitor = HealthMonitor::new(HealthConfig::default());
+        let samples: Vec<u8> = (0..4096).map(|i| (i % 256) as u8).collect();
+        monitor.observe(&samples);
+        assert_eq!(monitor.last_alarm(), None);
+    }
+
+    #[test]
+    fn reset_clears_state_and_alarm() {
+        let mut monitor = HealthMonitor::new(HealthConfig::default());
+        let stuck = vec![0x01u8; monitor.rct_cutoff + 1];
+        monitor.observe(&stuck);
+        assert!(monitor.last_alarm().is_some());
+        monitor.reset();
+        assert_eq!(monitor.last_alarm(), None);
+        assert_eq!(monitor.run_length, 0);
+    }
+}
@@ -1,3 +1,4 @@
+#[cfg(windows)]
 fn main() -> anyhow::Result<()> {
     let qrng = med_qrng::MedQrng::new()?;
 
@@ -37,3 +38,8 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("qrng-rust requires Windows: the QWQNG device is exposed only over a Windows COM object.");
+}
@@ -0,0 +1,598 @@
+use crate::retry::RetryPolicy;
+use crate::traits::SyncQrng;
+use anyhow::{anyhow, Result};
+use rand_core::{CryptoRng, Error as RandCoreError, RngCore};
+use std::cell::Cell;
+use std::thread;
+use std::{mem, ptr};
+use uuid::Uuid;
+use winapi::{
+    ctypes::c_void,
+    shared::guiddef::GUID,
+    um::{
+        combaseapi::{CoCreateInstance, CoInitializeEx, CoUninitialize},
+        cguid::GUID_NULL, // Import GUID_NULL
+        oleauto::{SafeArrayAccessData, SafeArrayGetLBound, SafeArrayGetUBound, SafeArrayUnaccessData, SysStringLen},
+        oaidl::{IDispatch, DISPPARAMS, VARIANT},
+        oaidl::SAFEARRAY, // Import SAFEARRAY from the public module
+        objbase::COINIT_APARTMENTTHREADED,
+        winnt::HRESULT,
+    },
+};
+
+///////////////////////////////////////////////////////////////////////////////
+// Constants for COM and VARIANT types
+///////////////////////////////////////////////////////////////////////////////
+
+pub const CLSCTX_INPROC_SERVER: u32 = 0x1;
+const S_OK: HRESULT = 0;
+const DISPATCH_PROPERTYGET: u16 = 2;
+const DISPATCH_METHOD: u16 = 1;
+
+const VT_I4: u16 = 3;    // 32-bit integer
+const VT_R8: u16 = 5;    // Double (f64)
+const VT_R4: u16 = 4;    // 32-bit float (f32)
+const VT_BSTR: u16 = 8;  // BSTR (wide string)
+const VT_ARRAY: u16 = 0x2000; // Flag indicating SAFEARRAY
+const VT_UI1: u16 = 17;  // Unsigned 8-bit integer
+
+const LOCALE_USER_DEFAULT: u32 = 0x0400;
+
+pub const IID_IDISPATCH: GUID = GUID {
+    Data1: 0x00020400,
+    Data2: 0x0000,
+    Data3: 0x0000,
+    Data4: [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+};
+
+///////////////////////////////////////////////////////////////////////////////
+// QWQNG Library Struct
+///////////////////////////////////////////////////////////////////////////////
+
+pub struct MedQrng {
+    p_disp: Cell<*mut IDispatch>,
+    retry_policy: RetryPolicy,
+}
+
+/// Builder for [`MedQrng`], currently used to configure its retry policy.
+pub struct MedQrngBuilder {
+    retry_policy: RetryPolicy,
+}
+
+impl MedQrngBuilder {
+    fn new() -> Self {
+        Self {
+            retry_policy: RetryPolicy::none(),
+        }
+    }
+
+    /// Sets the policy used to retry a transient HRESULT from `Invoke`.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Creates and initializes the QWQNG COM object with this builder's
+    /// configuration, bound to whichever device the driver selects by
+    /// default.
+    pub fn build(self) -> Result<MedQrng> {
+        MedQrng::new_with_policy(self.retry_policy)
+    }
+
+    /// Builds a handle bound to the device at `index`, as reported by
+    /// [`MedQrng::list_devices`].
+    pub fn open_by_index(self, index: i32) -> Result<MedQrng> {
+        let med = self.build()?;
+        med.select_device(index)?;
+        Ok(med)
+    }
+
+    /// Builds a handle bound to the device whose `DeviceId` serial matches
+    /// `serial`. Errors if no attached device reports that serial.
+    pub fn open_by_id(self, serial: &str) -> Result<MedQrng> {
+        let retry_policy = self.retry_policy.clone();
+        let devices = MedQrng::list_devices()?;
+        let index = devices
+            .iter()
+            .position(|id| id == serial)
+            .ok_or_else(|| anyhow!("no QWQNG device with DeviceId '{}' found", serial))?;
+        MedQrngBuilder { retry_policy }.open_by_index(index as i32)
+    }
+}
+
+impl MedQrng {
+    /// Starts building a `MedQrng` with a non-default configuration (e.g. a
+    /// retry policy, or a specific device to bind to).
+    pub fn builder() -> MedQrngBuilder {
+        MedQrngBuilder::new()
+    }
+
+    /// Binds to the device at `index` among those attached. See
+    /// [`MedQrng::list_devices`] for the index-to-serial mapping.
+    pub fn open_by_index(index: i32) -> Result<Self> {
+        Self::builder().open_by_index(index)
+    }
+
+    /// Binds to the device whose `DeviceId` serial matches `serial`.
+    pub fn open_by_id(serial: &str) -> Result<Self> {
+        Self::builder().open_by_id(serial)
+    }
+
+    /// Lists the `DeviceId` serial of every attached device, in driver
+    /// enumeration order; the position in this list is the `index` that
+    /// [`MedQrng::open_by_index`] expects.
+    pub fn list_devices() -> Result<Vec<String>> {
+        let probe = MedQrng::new()?;
+        let count = probe.device_count()?;
+        let mut ids = Vec::with_capacity(count.max(0) as usize);
+        for index in 0..count {
+            probe.select_device(index)?;
+            ids.push(probe.device_id()?);
+        }
+        Ok(ids)
+    }
+
+    /// Retrieves the number of attached devices from the DeviceCount
+    /// property.
+    pub fn device_count(&self) -> Result<i32> {
+        let var = self.invoke_property("DeviceCount", &[])?;
+        unsafe {
+            if var.n1.n2().vt == VT_I4 {
+                Ok(*var.n1.n2().n3.lVal())
+            } else {
+                Err(anyhow!("DeviceCount returned non-i32 type"))
+            }
+        }
+    }
+
+    /// Rebinds this handle's subsequent calls to the device at `index`, via
+    /// the SelectDevice method.
+    fn select_device(&self, index: i32) -> Result<()> {
+        self.invoke_method("SelectDevice", &[i32_variant(index)])
+    }
+
+    /// Creates and initializes the QWQNG COM object with the default
+    /// (no-retry) policy.
+    pub fn new() -> Result<Self> {
+        Self::new_with_policy(RetryPolicy::none())
+    }
+
+    fn new_with_policy(retry_policy: RetryPolicy) -> Result<Self> {
+        unsafe {
+            let hr = CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED);
+            if hr != S_OK {
+                return Err(anyhow!("CoInitializeEx failed: 0x{:08X}", hr));
+            }
+            let p_disp = match create_instance() {
+                Ok(p_disp) => p_disp,
+                Err(err) => {
+                    CoUninitialize();
+                    return Err(err);
+                }
+            };
+            Ok(Self {
+                p_disp: Cell::new(p_disp),
+                retry_policy,
+            })
+        }
+    }
+
+    /// Retrieves a 32-bit random integer from the RandInt32 property.
+    pub fn rand_int32(&self) -> Result<i32> {
+        let var = self.invoke_property("RandInt32", &[])?;
+        unsafe {
+            if var.n1.n2().vt == VT_I4 {
+                Ok(*var.n1.n2().n3.lVal())
+            } else {
+                Err(anyhow!("RandInt32 returned non-i32 type"))
+            }
+        }
+    }
+
+    /// Retrieves a uniform random double (in [0,1)) from RandUniform.
+    pub fn rand_uniform(&self) -> Result<f64> {
+        let var = self.invoke_property("RandUniform", &[])?;
+        unsafe {
+            if var.n1.n2().vt == VT_R8 {
+                Ok(*var.n1.n2().n3.dblVal())
+            } else {
+                Err(anyhow!("RandUniform returned non-f64 type"))
+            }
+        }
+    }
+
+    /// Retrieves a normally distributed random double from RandNormal.
+    pub fn rand_normal(&self) -> Result<f64> {
+        let var = self.invoke_property("RandNormal", &[])?;
+        unsafe {
+            if var.n1.n2().vt == VT_R8 {
+                Ok(*var.n1.n2().n3.dblVal())
+            } else {
+                Err(anyhow!("RandNormal returned non-f64 type"))
+            }
+        }
+    }
+
+    /// Retrieves random bytes (SAFEARRAY of VT_UI1) from RandBytes.
+    /// Pass the desired byte length as an argument.
+    pub fn rand_bytes(&self, length: i32) -> Result<Vec<u8>> {
+        let var = self.invoke_property_with_i32_arg("RandBytes", length)?;
+        variant_to_byte_array(&var)
+    }
+
+    /// Retrieves the device serial number (BSTR) from DeviceId.
+    pub fn device_id(&self) -> Result<String> {
+        let var = self.invoke_property("DeviceId", &[])?;
+        variant_to_bstr(&var)
+    }
+
+    /// Retrieves runtime info (SAFEARRAY of VT_R4) from RuntimeInfo.
+    pub fn runtime_info(&self) -> Result<Vec<f32>> {
+        let var = self.invoke_property("RuntimeInfo", &[])?;
+        variant_to_f32_array(&var)
+    }
+
+    /// Retrieves diagnostics data (SAFEARRAY of VT_UI1) from Diagnostics.
+    /// In our implementation Diagnostics is invoked as a method.
+    pub fn diagnostics(&self, dx_code: i32) -> Result<Vec<u8>> {
+        let var = self.invoke_method_with_i32_arg("Diagnostics", dx_code)?;
+        variant_to_byte_array(&var)
+    }
+
+    /// Calls the Clear() method.
+    pub fn clear(&self) -> Result<()> {
+        self.invoke_method("Clear", &[])?;
+        Ok(())
+    }
+
+    /// Calls the Reset() method.
+    pub fn reset(&self) -> Result<()> {
+        self.invoke_method("Reset", &[])?;
+        Ok(())
+    }
+}
+
+/// `MedQrng` is the hardware-backed implementor of `SyncQrng`; the trait
+/// methods simply delegate to the inherent ones above so existing callers
+/// that don't import the trait keep working unchanged.
+impl SyncQrng for MedQrng {
+    fn rand_int32(&self) -> Result<i32> {
+        MedQrng::rand_int32(self)
+    }
+
+    fn rand_uniform(&self) -> Result<f64> {
+        MedQrng::rand_uniform(self)
+    }
+
+    fn rand_normal(&self) -> Result<f64> {
+        MedQrng::rand_normal(self)
+    }
+
+    fn rand_bytes(&self, length: i32) -> Result<Vec<u8>> {
+        MedQrng::rand_bytes(self, length)
+    }
+
+    fn device_id(&self) -> Result<String> {
+        MedQrng::device_id(self)
+    }
+
+    fn clear(&self) -> Result<()> {
+        MedQrng::clear(self)
+    }
+
+    fn reset(&self) -> Result<()> {
+        MedQrng::reset(self)
+    }
+}
+
+impl Drop for MedQrng {
+    fn drop(&mut self) {
+        unsafe {
+            CoUninitialize();
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// rand_core::RngCore: makes MedQrng a drop-in source for the `rand` ecosystem
+///////////////////////////////////////////////////////////////////////////////
+
+impl RngCore for MedQrng {
+    fn next_u32(&mut self) -> u32 {
+        SyncQrng::rand_int32(self).expect("RandInt32 invoke failed") as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let hi = self.next_u32() as u64;
+        let lo = self.next_u32() as u64;
+        (hi << 32) | lo
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("RandBytes invoke failed")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RandCoreError> {
+        let bytes = SyncQrng::rand_bytes(self, dest.len() as i32).map_err(RandCoreError::new)?;
+        if bytes.len() != dest.len() {
+            return Err(RandCoreError::new(anyhow!(
+                "RandBytes returned {} bytes, expected {}",
+                bytes.len(),
+                dest.len()
+            )));
+        }
+        dest.copy_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+/// The QWQNG device is a physical hardware generator, so its output is
+/// suitable wherever a cryptographically secure source is required.
+impl CryptoRng for MedQrng {}
+
+///////////////////////////////////////////////////////////////////////////////
+// Helper: Invoking COM Properties and Methods
+///////////////////////////////////////////////////////////////////////////////
+
+/// Why a single `invoke_once` attempt failed. `GetIdsOfNames` failures
+/// aren't retried (a bad member name won't start working after a
+/// reconnect); a bad `Invoke` HRESULT might be, per the retry policy.
+enum InvokeFailure {
+    GetIdsOfNames(anyhow::Error),
+    Hresult(HRESULT),
+}
+
+impl MedQrng {
+    /// Invokes a COM property (DISPATCH_PROPERTYGET) with optional arguments.
+    fn invoke_property(&self, name: &str, args: &[VARIANT]) -> Result<VARIANT> {
+        self.invoke_with_retry(name, args, DISPATCH_PROPERTYGET)
+    }
+
+    /// Invokes a COM property with a single i32 argument.
+    fn invoke_property_with_i32_arg(&self, name: &str, arg: i32) -> Result<VARIANT> {
+        self.invoke_property(name, &[i32_variant(arg)])
+    }
+
+    /// Invokes a COM method (DISPATCH_METHOD) with optional arguments.
+    /// This version does not expect a return value.
+    fn invoke_method(&self, name: &str, args: &[VARIANT]) -> Result<()> {
+        let _ = self.invoke_method_return(name, args)?;
+        Ok(())
+    }
+
+    /// Invokes a COM method (DISPATCH_METHOD) with optional arguments and returns the VARIANT.
+    fn invoke_method_return(&self, name: &str, args: &[VARIANT]) -> Result<VARIANT> {
+        self.invoke_with_retry(name, args, DISPATCH_METHOD)
+    }
+
+    /// Invokes a COM method with a single i32 argument and returns the VARIANT.
+    fn invoke_method_with_i32_arg(&self, name: &str, arg: i32) -> Result<VARIANT> {
+        self.invoke_method_return(name, &[i32_variant(arg)])
+    }
+
+    /// Invokes `name` (as either a property-get or a method call, per
+    /// `kind`), applying `self.retry_policy` on a retryable HRESULT: the
+    /// device is reconnected (`Reset()` then a fresh `CoCreateInstance`)
+    /// and the call re-run, up to the policy's attempt limit.
+    fn invoke_with_retry(&self, name: &str, args: &[VARIANT], kind: u16) -> Result<VARIANT> {
+        let mut attempt = 1;
+        loop {
+            match unsafe { self.invoke_once(name, args, kind) } {
+                Ok(var) => return Ok(var),
+                Err(InvokeFailure::GetIdsOfNames(err)) => return Err(err),
+                Err(InvokeFailure::Hresult(hr))
+                    if attempt < self.retry_policy.max_attempts && self.retry_policy.is_retryable(hr) =>
+                {
+                    if !self.retry_policy.backoff.is_zero() {
+                        thread::sleep(self.retry_policy.backoff);
+                    }
+                    self.reconnect()?;
+                    attempt += 1;
+                }
+                Err(InvokeFailure::Hresult(hr)) => {
+                    return Err(anyhow!("Invoke('{}') failed: 0x{:08X}", name, hr))
+                }
+            }
+        }
+    }
+
+    /// A single, non-retried `Invoke` call.
+    unsafe fn invoke_once(&self, name: &str, args: &[VARIANT], kind: u16) -> std::result::Result<VARIANT, InvokeFailure> {
+        let p_disp = self.p_disp.get();
+        let dispid = get_dispid(p_disp, name).map_err(InvokeFailure::GetIdsOfNames)?;
+        let mut dp: DISPPARAMS = mem::zeroed();
+        if !args.is_empty() {
+            dp.rgvarg = args.as_ptr() as *mut VARIANT;
+            dp.cArgs = args.len() as u32;
+        }
+        let mut var_result: VARIANT = mem::zeroed();
+        let hr = (*p_disp).Invoke(
+            dispid,
+            &GUID_NULL,
+            LOCALE_USER_DEFAULT,
+            kind,
+            &mut dp,
+            &mut var_result,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        if hr == S_OK {
+            Ok(var_result)
+        } else {
+            Err(InvokeFailure::Hresult(hr))
+        }
+    }
+
+    /// Rebuilds the COM object in place: best-effort `Reset()` on the
+    /// existing pointer (ignored if it fails, since the connection may
+    /// already be too broken to answer), then a fresh `CoCreateInstance`,
+    /// releasing the old pointer once the new one is installed so the
+    /// retry loop doesn't leak a COM reference on every reconnect.
+    fn reconnect(&self) -> Result<()> {
+        unsafe {
+            let old_p_disp = self.p_disp.get();
+            let _ = self.invoke_once("Reset", &[], DISPATCH_METHOD);
+            let p_disp = create_instance()?;
+            self.p_disp.set(p_disp);
+            if !old_p_disp.is_null() {
+                (*old_p_disp).Release();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn i32_variant(value: i32) -> VARIANT {
+    let mut var: VARIANT = unsafe { mem::zeroed() };
+    unsafe {
+        var.n1.n2_mut().vt = VT_I4;
+        *var.n1.n2_mut().n3.lVal_mut() = value;
+    }
+    var
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Helper: Get QWQNG CLSID (hardcoded)
+///////////////////////////////////////////////////////////////////////////////
+
+fn get_qwqng_clsid() -> Result<GUID> {
+    let clsid_str = "{D7A1BFCF-9A30-45AF-A5E4-2CAF0A344938}";
+    let uuid = Uuid::parse_str(clsid_str.trim())?;
+    Ok(uuid_to_winapi_guid(&uuid))
+}
+
+/// Binds a fresh `IDispatch` pointer to the QWQNG COM object. Used both by
+/// `MedQrng::new` and by the retry policy's reconnect path.
+unsafe fn create_instance() -> Result<*mut IDispatch> {
+    let clsid = get_qwqng_clsid()?;
+    let mut p_disp: *mut IDispatch = ptr::null_mut();
+    let hr = CoCreateInstance(
+        &clsid,
+        ptr::null_mut(),
+        CLSCTX_INPROC_SERVER,
+        &IID_IDISPATCH,
+        &mut p_disp as *mut *mut IDispatch as *mut *mut c_void,
+    );
+    if hr != S_OK {
+        return Err(anyhow!("CoCreateInstance failed: 0x{:08X}", hr));
+    }
+    Ok(p_disp)
+}
+
+fn uuid_to_winapi_guid(u: &Uuid) -> GUID {
+    let b = u.as_bytes();
+    GUID {
+        Data1: u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+        Data2: u16::from_be_bytes([b[4], b[5]]),
+        Data3: u16::from_be_bytes([b[6], b[7]]),
+        Data4: [b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]],
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Helper: Get DISP ID for a COM member
+///////////////////////////////////////////////////////////////////////////////
+
+unsafe fn get_dispid(p_disp: *mut IDispatch, name: &str) -> Result<i32> {
+    let wide_name = to_utf16(name);
+    let mut dispid = 0i32;
+    let mut rgsz_names = [wide_name.as_ptr() as *mut u16];
+    let hr = (*p_disp).GetIDsOfNames(
+        &GUID_NULL,
+        rgsz_names.as_mut_ptr(),
+        1,
+        LOCALE_USER_DEFAULT,
+        &mut dispid,
+    );
+    if hr == S_OK {
+        Ok(dispid)
+    } else {
+        Err(anyhow!("GetIDsOfNames('{}') failed: 0x{:08X}", name, hr))
+    }
+}
+
+fn to_utf16(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// SAFEARRAY / BSTR Parsing Functions
+///////////////////////////////////////////////////////////////////////////////
+
+fn variant_to_byte_array(var: &VARIANT) -> Result<Vec<u8>> {
+    unsafe {
+        let vt = var.n1.n2().vt;
+        if (vt & VT_ARRAY) != VT_ARRAY || (vt & VT_UI1) != VT_UI1 {
+            return Err(anyhow!("Expected SAFEARRAY of bytes, but got vt=0x{:X}", vt));
+        }
+        // Get the SAFEARRAY pointer by calling parray() and dereferencing
+        let psa: *mut SAFEARRAY = *var.n1.n2().n3.parray();
+        if psa.is_null() {
+            return Err(anyhow!("Null SAFEARRAY pointer"));
+        }
+        let mut lbound: i32 = 0;
+        let mut ubound: i32 = 0;
+        let hr_lb = SafeArrayGetLBound(psa, 1, &mut lbound as *mut i32);
+        let hr_ub = SafeArrayGetUBound(psa, 1, &mut ubound as *mut i32);
+        if hr_lb != S_OK || hr_ub != S_OK {
+            return Err(anyhow!("SafeArrayGetLBound/UBound failed"));
+        }
+        let count = (ubound - lbound + 1) as usize;
+        let mut data_ptr: *mut u8 = ptr::null_mut();
+        let hr_access = SafeArrayAccessData(psa, &mut data_ptr as *mut *mut u8 as *mut *mut c_void);
+        if hr_access != S_OK {
+            return Err(anyhow!("SafeArrayAccessData failed"));
+        }
+        let slice = std::slice::from_raw_parts(data_ptr, count);
+        let bytes = slice.to_vec();
+        SafeArrayUnaccessData(psa);
+        Ok(bytes)
+    }
+}
+
+fn variant_to_bstr(var: &VARIANT) -> Result<String> {
+    unsafe {
+        let vt = var.n1.n2().vt;
+        if vt != VT_BSTR {
+            return Err(anyhow!("Expected BSTR, but got vt=0x{:X}", vt));
+        }
+        let bstr_ptr = *var.n1.n2().n3.bstrVal();
+        if bstr_ptr.is_null() {
+            return Ok(String::new());
+        }
+        let len = SysStringLen(bstr_ptr) as usize;
+        let slice = std::slice::from_raw_parts(bstr_ptr, len);
+        let rust_string = String::from_utf16_lossy(slice);
+        Ok(rust_string)
+    }
+}
+
+fn variant_to_f32_array(var: &VARIANT) -> Result<Vec<f32>> {
+    unsafe {
+        let vt = var.n1.n2().vt;
+        if (vt & VT_ARRAY) != VT_ARRAY || (vt & VT_R4) != VT_R4 {
+            return Err(anyhow!("Expected SAFEARRAY of f32 (VT_ARRAY|VT_R4), got vt=0x{:X}", vt));
+        }
+        let psa: *mut SAFEARRAY = *var.n1.n2().n3.parray();
+        if psa.is_null() {
+            return Err(anyhow!("Null SAFEARRAY pointer for float array"));
+        }
+        let mut lbound: i32 = 0;
+        let mut ubound: i32 = 0;
+        if SafeArrayGetLBound(psa, 1, &mut lbound as *mut i32) != S_OK {
+            return Err(anyhow!("SafeArrayGetLBound failed"));
+        }
+        if SafeArrayGetUBound(psa, 1, &mut ubound as *mut i32) != S_OK {
+            return Err(anyhow!("SafeArrayGetUBound failed"));
+        }
+        let count = (ubound - lbound + 1) as usize;
+        let mut data_ptr: *mut f32 = ptr::null_mut();
+        if SafeArrayAccessData(psa, &mut data_ptr as *mut *mut f32 as *mut *mut c_void) != S_OK {
+            return Err(anyhow!("SafeArrayAccessData failed on float array"));
+        }
+        let slice = std::slice::from_raw_parts(data_ptr, count);
+        let floats = slice.to_vec();
+        SafeArrayUnaccessData(psa);
+        Ok(floats)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::traits::SyncQrng;
+use anyhow::Result;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::cell::RefCell;
+
+///////////////////////////////////////////////////////////////////////////////
+// Mock backend: deterministic/seeded software randomness for non-Windows
+// development and unit tests that don't have a device attached.
+///////////////////////////////////////////////////////////////////////////////
+
+/// Software-only `SyncQrng` implementation backed by a seeded PRNG.
+///
+/// Not cryptographically or physically random — it exists so downstream
+/// crates can compile and exercise their logic against `T: Qrng` on any
+/// platform, without a QWQNG device attached.
+pub struct MockQrng {
+    rng: RefCell<StdRng>,
+    device_id: String,
+}
+
+impl MockQrng {
+    /// Creates a mock backend seeded from the OS entropy source.
+    pub fn new() -> Self {
+        Self::from_seed(rand::random())
+    }
+
+    /// Creates a mock backend with a fixed seed, for reproducible tests.
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+            device_id: format!("MOCK-{:016X}", seed),
+        }
+    }
+}
+
+impl Default for MockQrng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncQrng for MockQrng {
+    fn rand_int32(&self) -> Result<i32> {
+        Ok(self.rng.borrow_mut().gen())
+    }
+
+    fn rand_uniform(&self) -> Result<f64> {
+        Ok(self.rng.borrow_mut().gen_range(0.0..1.0))
+    }
+
+    fn rand_normal(&self) -> Result<f64> {
+        // Box-Muller transform over two uniform samples; good enough for a
+        // software stand-in, not a fit to the device's actual distribution.
+        let mut rng = self.rng.borrow_mut();
+        let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = rng.gen_range(0.0..1.0);
+        Ok((-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos())
+    }
+
+    fn rand_bytes(&self, length: i32) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; length.max(0) as usize];
+        self.rng.borrow_mut().fill(buf.as_mut_slice());
+        Ok(buf)
+    }
+
+    fn device_id(&self) -> Result<String> {
+        Ok(self.device_id.clone())
+    }
+
+    fn clear(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn reset(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_backend_is_deterministic() {
+        let a = MockQrng::from_seed(42);
+        let b = MockQrng::from_seed(42);
+        assert_eq!(a.rand_int32().unwrap(), b.rand_int32().unwrap());
+        assert_eq!(a.rand_bytes(16).unwrap(), b.rand_bytes(16).unwrap());
+    }
+
+    #[test]
+    fn rand_uniform_stays_in_unit_range() {
+        let qrng = MockQrng::from_seed(7);
+        for _ in 0..1000 {
+            let v = qrng.rand_uniform().unwrap();
+            assert!((0.0..1.0).contains(&v));
+        }
+    }
+
+    #[test]
+    fn rand_bytes_respects_length() {
+        let qrng = MockQrng::from_seed(1);
+        assert_eq!(qrng.rand_bytes(32).unwrap().len(), 32);
+        assert_eq!(qrng.rand_bytes(0).unwrap().len(), 0);
+    }
+}
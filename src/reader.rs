@@ -0,0 +1,88 @@
+use crate::traits::SyncQrng;
+use std::io::{self, Read};
+
+///////////////////////////////////////////////////////////////////////////////
+// std::io::Read adapter with internal buffering
+///////////////////////////////////////////////////////////////////////////////
+
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// Buffers a backend's `rand_bytes` behind `std::io::Read`.
+///
+/// Reads smaller than the buffer are served from memory; only an empty
+/// buffer triggers a fresh `RandBytes` call, amortizing the per-call COM
+/// dispatch overhead across many small reads. Works with any backend —
+/// `MedQrng`, `MockQrng`, or a future one — via `T: SyncQrng`.
+pub struct QrngReader<T: SyncQrng> {
+    backend: T,
+    capacity: usize,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<T: SyncQrng> QrngReader<T> {
+    /// Wraps `backend` with the default 4 KiB refill buffer.
+    pub fn new(backend: T) -> Self {
+        Self::with_capacity(backend, DEFAULT_CAPACITY)
+    }
+
+    /// Wraps `backend` with a refill buffer of `capacity` bytes.
+    pub fn with_capacity(backend: T, capacity: usize) -> Self {
+        Self {
+            backend,
+            capacity,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Returns the wrapped backend, discarding any buffered bytes.
+    pub fn into_inner(self) -> T {
+        self.backend
+    }
+
+    fn refill(&mut self) -> io::Result<()> {
+        self.buf = self
+            .backend
+            .rand_bytes(self.capacity as i32)
+            .map_err(io::Error::other)?;
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<T: SyncQrng> Read for QrngReader<T> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.buf.len() {
+            self.refill()?;
+        }
+        let available = &self.buf[self.pos..];
+        let n = available.len().min(out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::MockQrng;
+
+    #[test]
+    fn reads_exact_requested_length() {
+        let mut reader = QrngReader::with_capacity(MockQrng::from_seed(1), 8);
+        let mut out = [0u8; 20];
+        reader.read_exact(&mut out).unwrap();
+    }
+
+    #[test]
+    fn refills_once_buffer_is_exhausted() {
+        let mut reader = QrngReader::with_capacity(MockQrng::from_seed(2), 4);
+        let mut first = [0u8; 4];
+        let mut second = [0u8; 4];
+        reader.read_exact(&mut first).unwrap();
+        reader.read_exact(&mut second).unwrap();
+        assert_ne!(first, second);
+    }
+}
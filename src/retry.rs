@@ -0,0 +1,91 @@
+use std::time::Duration;
+use winapi::um::winnt::HRESULT;
+
+///////////////////////////////////////////////////////////////////////////////
+// Retry/reconnect policy around COM Invoke calls
+///////////////////////////////////////////////////////////////////////////////
+
+/// Governs how `MedQrng` responds to a transient HRESULT from `Invoke`.
+///
+/// Built up via the setter methods and handed to [`crate::MedQrngBuilder`];
+/// the default policy never retries, matching the original fail-fast
+/// behavior.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    pub(crate) backoff: Duration,
+    pub(crate) retryable_hresults: Vec<HRESULT>,
+}
+
+impl RetryPolicy {
+    /// No retries: the first failing HRESULT is returned immediately.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+            retryable_hresults: Vec::new(),
+        }
+    }
+
+    /// Total number of attempts (including the first) before giving up.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay between a failed attempt and the next retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Adds an HRESULT that should trigger a reconnect-and-retry rather
+    /// than an immediate error.
+    pub fn retry_on(mut self, hresult: HRESULT) -> Self {
+        self.retryable_hresults.push(hresult);
+        self
+    }
+
+    pub(crate) fn is_retryable(&self, hresult: HRESULT) -> bool {
+        self.retryable_hresults.contains(&hresult)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_never_retries_and_has_no_retryable_codes() {
+        let policy = RetryPolicy::none();
+        assert_eq!(policy.max_attempts, 1);
+        assert_eq!(policy.backoff, Duration::ZERO);
+        assert!(!policy.is_retryable(0x8000_FFFF_u32 as HRESULT));
+    }
+
+    #[test]
+    fn default_matches_none() {
+        assert_eq!(RetryPolicy::default().max_attempts, RetryPolicy::none().max_attempts);
+    }
+
+    #[test]
+    fn max_attempts_is_clamped_to_at_least_one() {
+        let policy = RetryPolicy::none().max_attempts(0);
+        assert_eq!(policy.max_attempts, 1);
+    }
+
+    #[test]
+    fn retry_on_registers_only_the_given_hresults() {
+        let retryable: HRESULT = 0x8000_0001_u32 as HRESULT;
+        let other: HRESULT = 0x8000_0002_u32 as HRESULT;
+        let policy = RetryPolicy::none().retry_on(retryable);
+        assert!(policy.is_retryable(retryable));
+        assert!(!policy.is_retryable(other));
+    }
+}
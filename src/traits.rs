@@ -0,0 +1,42 @@
+use anyhow::Result;
+
+///////////////////////////////////////////////////////////////////////////////
+// Backend-agnostic QRNG traits
+///////////////////////////////////////////////////////////////////////////////
+
+/// Blocking entropy source.
+///
+/// Implemented by every blocking backend in this crate (the real device,
+/// mocks, and anything else that can hand back random integers/bytes on
+/// demand); the async `AsyncQrng` handle implements only [`Qrng`].
+/// Generic code should depend on `T: SyncQrng` rather than a concrete
+/// backend so it can be swapped or substituted with a mock in tests.
+pub trait SyncQrng {
+    /// Returns a uniformly distributed random 32-bit integer.
+    fn rand_int32(&self) -> Result<i32>;
+
+    /// Returns a uniform random double in `[0, 1)`.
+    fn rand_uniform(&self) -> Result<f64>;
+
+    /// Returns a normally distributed random double.
+    fn rand_normal(&self) -> Result<f64>;
+
+    /// Returns `length` random bytes.
+    fn rand_bytes(&self, length: i32) -> Result<Vec<u8>>;
+
+    /// Returns the backend's device/serial identifier.
+    fn device_id(&self) -> Result<String>;
+
+    /// Clears any internal buffers held by the backend.
+    fn clear(&self) -> Result<()>;
+
+    /// Resets the backend to its initial state.
+    fn reset(&self) -> Result<()>;
+}
+
+/// Umbrella trait for anything that can serve as a QRNG backend, sync or
+/// async. Blanket-implemented for every `SyncQrng`; `AsyncQrng` implements
+/// it directly, since its command-channel API isn't itself a `SyncQrng`.
+pub trait Qrng {}
+
+impl<T: SyncQrng> Qrng for T {}